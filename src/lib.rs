@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::fmt;
+use std::io::{Read, Write};
 use std::ops::Deref;
 
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
@@ -13,6 +15,30 @@ pub enum BitcoinError {
     InvalidFormat,
 }
 
+/// Smallest possible serialized size of a `TransactionInput`: a 36-byte
+/// `OutPoint`, a 1-byte CompactSize for an empty `script_sig`, and a 4-byte
+/// sequence number.
+const MIN_INPUT_SERIALIZED_SIZE: usize = 41;
+
+/// Smallest possible serialized size of a `TransactionOutput`: an 8-byte
+/// value and a 1-byte CompactSize for an empty `script_pubkey`.
+const MIN_OUTPUT_SERIALIZED_SIZE: usize = 9;
+
+/// Consensus serialization to a writer, mirroring `rust-bitcoin`'s
+/// `ConsensusEncodable` and Zebra's `ZcashSerialize`. Returns the number of
+/// bytes written so callers can track position without building an
+/// intermediate `Vec<u8>`.
+pub trait Encode {
+    fn consensus_encode<W: Write>(&self, writer: &mut W) -> Result<usize, BitcoinError>;
+}
+
+/// Consensus deserialization from a reader, mirroring `rust-bitcoin`'s
+/// `ConsensusDecodable` and Zebra's `ZcashDeserialize`. Lets callers stream
+/// directly out of a socket or file instead of slicing a buffer by hand.
+pub trait Decode: Sized {
+    fn consensus_decode<R: Read>(reader: &mut R) -> Result<Self, BitcoinError>;
+}
+
 impl CompactSize {
     pub fn new(value: u64) -> Self {
         // TODO: Construct a CompactSize from a u64 value
@@ -20,13 +46,27 @@ impl CompactSize {
     }
 
     pub fn to_bytes(&self) -> Vec<u8> {
-        // TODO: Encode according to Bitcoin's CompactSize format:         // 'x' == 4 bits
-        // [0x00–0xFC] => 1 byte                                           // 0 -- 252
-        // [0xFDxxxx] => 0xFD + u16 (2 bytes)                              // 253 -- 65535
-        // [0xFExxxxxxxx] => 0xFE + u32 (4 bytes)                          // 65536 -- 4294967295
-        // [0xFFxxxxxxxxxxxxxxxx] => 0xFF + u64 (8 bytes)
+        let mut buf = Vec::new();
+        self.consensus_encode(&mut buf)
+            .expect("writes to a Vec<u8> are infallible");
+        buf
+    }
 
-        match self.value {
+    pub fn from_bytes(bytes: &[u8]) -> Result<(Self, usize), BitcoinError> {
+        let mut cursor = std::io::Cursor::new(bytes);
+        let value = Self::consensus_decode(&mut cursor)?;
+        Ok((value, cursor.position() as usize))
+    }
+}
+
+impl Encode for CompactSize {
+    fn consensus_encode<W: Write>(&self, writer: &mut W) -> Result<usize, BitcoinError> {
+        // Encode according to Bitcoin's CompactSize format:      // 'x' == 4 bits
+        // [0x00–0xFC] => 1 byte                                  // 0 -- 252
+        // [0xFDxxxx] => 0xFD + u16 (2 bytes)                     // 253 -- 65535
+        // [0xFExxxxxxxx] => 0xFE + u32 (4 bytes)                 // 65536 -- 4294967295
+        // [0xFFxxxxxxxxxxxxxxxx] => 0xFF + u64 (8 bytes)
+        let bytes = match self.value {
             0..=252 => vec![self.value as u8],
             253..=65535 => {
                 let mut v = vec![0xFD];
@@ -43,34 +83,35 @@ impl CompactSize {
                 v.extend_from_slice(&self.value.to_le_bytes());
                 v
             }
-        }
+        };
+        writer
+            .write_all(&bytes)
+            .map_err(|_| BitcoinError::InvalidFormat)?;
+        Ok(bytes.len())
     }
+}
 
-    pub fn from_bytes(bytes: &[u8]) -> Result<(Self, usize), BitcoinError> {
-        // TODO: Decode CompactSize, returning value and number of bytes consumed.
-        // First check if bytes is empty.
-        // Check that enough bytes are available based on prefix.
-        if bytes.is_empty() {
-            return Err(BitcoinError::InsufficientBytes);
-        }
-
-        match bytes[0] {
-            n @ 0x00..=0xFC => Ok((Self::new(n as u64), 1)),
-            0xFD => {
-                let val = u16::from_le_bytes([bytes[1], bytes[2]]) as u64;
-                Ok((Self::new(val), 3))
-            }
-            0xFE => {
-                let val = u32::from_le_bytes([bytes[1], bytes[2], bytes[3], bytes[4]]) as u64;
-                Ok((Self::new(val), 5))
-            }
-            0xFF => {
-                let val = u64::from_le_bytes([
-                    bytes[1], bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7], bytes[8],
-                ]);
-                Ok((Self::new(val), 9))
-            }
-        }
+impl Decode for CompactSize {
+    fn consensus_decode<R: Read>(reader: &mut R) -> Result<Self, BitcoinError> {
+        let mut prefix = [0u8; 1];
+        reader
+            .read_exact(&mut prefix)
+            .map_err(|_| BitcoinError::InsufficientBytes)?;
+
+        let extra = match prefix[0] {
+            0x00..=0xFC => 0,
+            0xFD => 2,
+            0xFE => 4,
+            0xFF => 8,
+        };
+        let mut buf = vec![0u8; 1 + extra];
+        buf[0] = prefix[0];
+        reader
+            .read_exact(&mut buf[1..])
+            .map_err(|_| BitcoinError::InsufficientBytes)?;
+
+        let (value, _) = decode_compact_size(&buf)?;
+        Ok(CompactSize::new(value))
     }
 }
 
@@ -106,6 +147,25 @@ impl<'de> Deserialize<'de> for Txid {
     }
 }
 
+impl Encode for Txid {
+    fn consensus_encode<W: Write>(&self, writer: &mut W) -> Result<usize, BitcoinError> {
+        writer
+            .write_all(&self.0)
+            .map_err(|_| BitcoinError::InvalidFormat)?;
+        Ok(32)
+    }
+}
+
+impl Decode for Txid {
+    fn consensus_decode<R: Read>(reader: &mut R) -> Result<Self, BitcoinError> {
+        let mut bytes = [0u8; 32];
+        reader
+            .read_exact(&mut bytes)
+            .map_err(|_| BitcoinError::InsufficientBytes)?;
+        Ok(Txid(bytes))
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct OutPoint {
     pub txid: Txid,
@@ -122,54 +182,90 @@ impl OutPoint {
     }
 
     pub fn to_bytes(&self) -> Vec<u8> {
-        // TODO: Serialize as: txid (32 bytes) + vout (4 bytes, little-endian)
-        let mut bytes = self.txid.0.to_vec(); // 32 bytes
-        bytes.extend(&self.vout.to_le_bytes()); // 4 bytes, little-endian
-        bytes
+        let mut buf = Vec::new();
+        self.consensus_encode(&mut buf)
+            .expect("writes to a Vec<u8> are infallible");
+        buf
     }
 
     pub fn from_bytes(bytes: &[u8]) -> Result<(Self, usize), BitcoinError> {
-        // TODO: Deserialize 36 bytes: txid[0..32], vout[32..36]
-        // Return error if insufficient bytes
-        if bytes.len() < 36 {
-            return Err(BitcoinError::InsufficientBytes);
-        }
-        let mut txid = [0u8; 32];
-        txid.copy_from_slice(&bytes[..32]);
-        let vout = u32::from_le_bytes(bytes[32..36].try_into().unwrap());
-        Ok((OutPoint::new(txid, vout), 36))
+        let mut cursor = std::io::Cursor::new(bytes);
+        let value = Self::consensus_decode(&mut cursor)?;
+        Ok((value, cursor.position() as usize))
+    }
+}
+
+impl Encode for OutPoint {
+    fn consensus_encode<W: Write>(&self, writer: &mut W) -> Result<usize, BitcoinError> {
+        // txid (32 bytes) + vout (4 bytes, little-endian)
+        let mut written = self.txid.consensus_encode(writer)?;
+        writer
+            .write_all(&self.vout.to_le_bytes())
+            .map_err(|_| BitcoinError::InvalidFormat)?;
+        written += 4;
+        Ok(written)
+    }
+}
+
+impl Decode for OutPoint {
+    fn consensus_decode<R: Read>(reader: &mut R) -> Result<Self, BitcoinError> {
+        let txid = Txid::consensus_decode(reader)?;
+        let mut vout_bytes = [0u8; 4];
+        reader
+            .read_exact(&mut vout_bytes)
+            .map_err(|_| BitcoinError::InsufficientBytes)?;
+        Ok(OutPoint {
+            txid,
+            vout: u32::from_le_bytes(vout_bytes),
+        })
     }
 }
 
+/// Bitcoin's hash256: SHA-256 applied twice. Used for txid/wtxid and block-hash
+/// derivation, and reusable for future Merkle-root work.
+fn double_sha256(bytes: &[u8]) -> [u8; 32] {
+    let first = Sha256::digest(bytes);
+    Sha256::digest(first).into()
+}
+
 fn decode_compact_size(bytes: &[u8]) -> Result<(u64, usize), BitcoinError> {
     if bytes.is_empty() {
         return Err(BitcoinError::InsufficientBytes);
     }
+    // Consensus code must reject non-canonical CompactSize encodings (e.g. 0xFD 0x05 0x00
+    // for the value 5) rather than silently accepting them, so each prefix is only valid
+    // for the value range it is the minimal encoding for.
     match bytes[0] {
         n @ 0x00..=0xFC => Ok((n as u64, 1)),
         0xFD => {
             if bytes.len() < 3 {
-                Err(BitcoinError::InsufficientBytes)
-            } else {
-                let val = u16::from_le_bytes(bytes[1..3].try_into().unwrap()) as u64;
-                Ok((val, 3))
+                return Err(BitcoinError::InsufficientBytes);
             }
+            let val = u16::from_le_bytes(bytes[1..3].try_into().unwrap()) as u64;
+            if val < 0xFD {
+                return Err(BitcoinError::InvalidFormat);
+            }
+            Ok((val, 3))
         }
         0xFE => {
             if bytes.len() < 5 {
-                Err(BitcoinError::InsufficientBytes)
-            } else {
-                let val = u32::from_le_bytes(bytes[1..5].try_into().unwrap()) as u64;
-                Ok((val, 5))
+                return Err(BitcoinError::InsufficientBytes);
+            }
+            let val = u32::from_le_bytes(bytes[1..5].try_into().unwrap()) as u64;
+            if val < 0x1_0000 {
+                return Err(BitcoinError::InvalidFormat);
             }
+            Ok((val, 5))
         }
         0xFF => {
             if bytes.len() < 9 {
-                Err(BitcoinError::InsufficientBytes)
-            } else {
-                let val = u64::from_le_bytes(bytes[1..9].try_into().unwrap());
-                Ok((val, 9))
+                return Err(BitcoinError::InsufficientBytes);
+            }
+            let val = u64::from_le_bytes(bytes[1..9].try_into().unwrap());
+            if val < 0x1_0000_0000 {
+                return Err(BitcoinError::InvalidFormat);
             }
+            Ok((val, 9))
         }
     }
 }
@@ -207,23 +303,41 @@ impl Script {
     }
 
     pub fn to_bytes(&self) -> Vec<u8> {
-        // TODO: Prefix with CompactSize (length), then raw bytes
-        let mut result = Vec::new();
-        result.extend(encode_compact_size(self.bytes.len() as u64));
-        result.extend(&self.bytes);
-        result
+        let mut buf = Vec::new();
+        self.consensus_encode(&mut buf)
+            .expect("writes to a Vec<u8> are infallible");
+        buf
     }
 
     pub fn from_bytes(bytes: &[u8]) -> Result<(Self, usize), BitcoinError> {
-        // TODO: Parse CompactSize prefix, then read that many bytes
-        // Return error if not enough bytes
-        let (len, prefix_len) = decode_compact_size(bytes)?;
-        let total_len = prefix_len + len as usize;
-        if bytes.len() < total_len {
-            return Err(BitcoinError::InsufficientBytes);
-        }
-        let script_bytes = bytes[prefix_len..total_len].to_vec();
-        Ok((Script::new(script_bytes), total_len))
+        let mut cursor = std::io::Cursor::new(bytes);
+        let value = Self::consensus_decode(&mut cursor)?;
+        Ok((value, cursor.position() as usize))
+    }
+}
+
+impl Encode for Script {
+    fn consensus_encode<W: Write>(&self, writer: &mut W) -> Result<usize, BitcoinError> {
+        // CompactSize length prefix, then raw bytes
+        let len_bytes = encode_compact_size(self.bytes.len() as u64);
+        writer
+            .write_all(&len_bytes)
+            .map_err(|_| BitcoinError::InvalidFormat)?;
+        writer
+            .write_all(&self.bytes)
+            .map_err(|_| BitcoinError::InvalidFormat)?;
+        Ok(len_bytes.len() + self.bytes.len())
+    }
+}
+
+impl Decode for Script {
+    fn consensus_decode<R: Read>(reader: &mut R) -> Result<Self, BitcoinError> {
+        let len = CompactSize::consensus_decode(reader)?.value;
+        let mut bytes = vec![0u8; len as usize];
+        reader
+            .read_exact(&mut bytes)
+            .map_err(|_| BitcoinError::InsufficientBytes)?;
+        Ok(Script::new(bytes))
     }
 }
 
@@ -235,48 +349,158 @@ impl Deref for Script {
     }
 }
 
+/// A per-input witness stack, as introduced by BIP-141. Each element is one
+/// item pushed to the stack; a legacy (non-SegWit) input has an empty stack.
+#[derive(Debug, PartialEq, Eq, Clone, Default, Serialize, Deserialize)]
+pub struct Witness(pub Vec<Vec<u8>>);
+
+impl Witness {
+    pub fn new(items: Vec<Vec<u8>>) -> Self {
+        Witness(items)
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        // CompactSize item count, then each item as CompactSize length + bytes
+        let mut result = encode_compact_size(self.0.len() as u64);
+        for item in &self.0 {
+            result.extend(encode_compact_size(item.len() as u64));
+            result.extend(item);
+        }
+        result
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<(Self, usize), BitcoinError> {
+        let (item_count, mut offset) = decode_compact_size(bytes)?;
+        let mut items = Vec::new();
+        for _ in 0..item_count {
+            let (len, len_size) = decode_compact_size(&bytes[offset..])?;
+            offset += len_size;
+            let item_end = offset + len as usize;
+            if bytes.len() < item_end {
+                return Err(BitcoinError::InsufficientBytes);
+            }
+            items.push(bytes[offset..item_end].to_vec());
+            offset = item_end;
+        }
+        Ok((Witness::new(items), offset))
+    }
+}
+
+impl Deref for Witness {
+    type Target = Vec<Vec<u8>>;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct TransactionInput {
     pub previous_output: OutPoint,
     pub script_sig: Script,
     pub sequence: u32,
+    pub witness: Witness,
 }
 
 impl TransactionInput {
-    pub fn new(previous_output: OutPoint, script_sig: Script, sequence: u32) -> Self {
+    pub fn new(
+        previous_output: OutPoint,
+        script_sig: Script,
+        sequence: u32,
+        witness: Witness,
+    ) -> Self {
         TransactionInput {
             previous_output,
             script_sig,
             sequence,
+            witness,
         }
     }
 
     pub fn to_bytes(&self) -> Vec<u8> {
-        // TODO: Serialize: OutPoint + Script (with CompactSize) + sequence (4 bytes LE)
-        let mut result = Vec::new();
-        result.extend(self.previous_output.to_bytes()); // 36 bytes
-        result.extend(self.script_sig.to_bytes()); // CompactSize + script bytes
-        result.extend(&self.sequence.to_le_bytes()); // 4 bytes, little-endian
-        result
+        let mut buf = Vec::new();
+        self.consensus_encode(&mut buf)
+            .expect("writes to a Vec<u8> are infallible");
+        buf
     }
 
     pub fn from_bytes(bytes: &[u8]) -> Result<(Self, usize), BitcoinError> {
-        // TODO: Deserialize in order:
+        let mut cursor = std::io::Cursor::new(bytes);
+        let value = Self::consensus_decode(&mut cursor)?;
+        Ok((value, cursor.position() as usize))
+    }
+}
+
+impl Encode for TransactionInput {
+    fn consensus_encode<W: Write>(&self, writer: &mut W) -> Result<usize, BitcoinError> {
+        // Legacy layout only: OutPoint + Script (with CompactSize) + sequence (4 bytes LE).
+        // The witness stack is serialized separately by BitcoinTransaction, after all
+        // inputs and outputs, as BIP-141 requires.
+        let mut written = self.previous_output.consensus_encode(writer)?; // 36 bytes
+        written += self.script_sig.consensus_encode(writer)?; // CompactSize + script bytes
+        writer
+            .write_all(&self.sequence.to_le_bytes())
+            .map_err(|_| BitcoinError::InvalidFormat)?;
+        written += 4;
+        Ok(written)
+    }
+}
+
+impl Decode for TransactionInput {
+    fn consensus_decode<R: Read>(reader: &mut R) -> Result<Self, BitcoinError> {
+        // Deserialize in order:
         // - OutPoint (36 bytes)
         // - Script (with CompactSize)
         // - Sequence (4 bytes)
-        let (outpoint, outpoint_len) = OutPoint::from_bytes(bytes)?;
-        let (script_sig, script_len) = Script::from_bytes(&bytes[outpoint_len..])?;
+        // The witness stack (if any) is filled in afterwards by BitcoinTransaction.
+        let previous_output = OutPoint::consensus_decode(reader)?;
+        let script_sig = Script::consensus_decode(reader)?;
 
-        let seq_start = outpoint_len + script_len;
-        if bytes.len() < seq_start + 4 {
-            return Err(BitcoinError::InsufficientBytes);
+        let mut sequence_bytes = [0u8; 4];
+        reader
+            .read_exact(&mut sequence_bytes)
+            .map_err(|_| BitcoinError::InsufficientBytes)?;
+        let sequence = u32::from_le_bytes(sequence_bytes);
+
+        Ok(TransactionInput::new(
+            previous_output,
+            script_sig,
+            sequence,
+            Witness::default(),
+        ))
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct TransactionOutput {
+    pub value: u64,
+    pub script_pubkey: Script,
+}
+
+impl TransactionOutput {
+    pub fn new(value: u64, script_pubkey: Script) -> Self {
+        TransactionOutput {
+            value,
+            script_pubkey,
         }
-        let sequence = u32::from_le_bytes(bytes[seq_start..seq_start + 4].try_into().unwrap());
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        // Value (8 bytes LE) + Script (CompactSize-prefixed)
+        let mut result = Vec::new();
+        result.extend(&self.value.to_le_bytes());
+        result.extend(self.script_pubkey.to_bytes());
+        result
+    }
 
+    pub fn from_bytes(bytes: &[u8]) -> Result<(Self, usize), BitcoinError> {
+        if bytes.len() < 8 {
+            return Err(BitcoinError::InsufficientBytes);
+        }
+        let value = u64::from_le_bytes(bytes[0..8].try_into().unwrap());
+        let (script_pubkey, script_len) = Script::from_bytes(&bytes[8..])?;
         Ok((
-            TransactionInput::new(outpoint, script_sig, sequence),
-            seq_start + 4,
+            TransactionOutput::new(value, script_pubkey),
+            8 + script_len,
         ))
     }
 }
@@ -285,25 +509,77 @@ impl TransactionInput {
 pub struct BitcoinTransaction {
     pub version: u32,
     pub inputs: Vec<TransactionInput>,
+    pub outputs: Vec<TransactionOutput>,
     pub lock_time: u32,
 }
 
 impl BitcoinTransaction {
-    pub fn new(version: u32, inputs: Vec<TransactionInput>, lock_time: u32) -> Self {
+    pub fn new(
+        version: u32,
+        inputs: Vec<TransactionInput>,
+        outputs: Vec<TransactionOutput>,
+        lock_time: u32,
+    ) -> Self {
         BitcoinTransaction {
             version,
             inputs,
+            outputs,
             lock_time,
         }
     }
 
+    /// Whether this transaction carries BIP-141 witness data on any input. A
+    /// transaction for which this is `false` serializes to the legacy layout.
+    fn is_segwit(&self) -> bool {
+        self.inputs.iter().any(|input| !input.witness.is_empty())
+    }
+
+    /// The transaction's identifier: double-SHA-256 of the legacy
+    /// (witness-stripped) serialization, byte-reversed for display, as in
+    /// `rust-bitcoin`. Equal to `wtxid()` when there is no witness data.
+    pub fn txid(&self) -> Txid {
+        let mut hash = double_sha256(&self.legacy_bytes());
+        hash.reverse();
+        Txid(hash)
+    }
+
+    /// The transaction's witness identifier: double-SHA-256 of the full
+    /// SegWit serialization, byte-reversed for display. Equal to `txid()`
+    /// when there is no witness data.
+    pub fn wtxid(&self) -> Txid {
+        let mut hash = double_sha256(&self.to_bytes());
+        hash.reverse();
+        Txid(hash)
+    }
+
     pub fn to_bytes(&self) -> Vec<u8> {
-        // TODO: Format:
+        self.serialize(self.is_segwit())
+    }
+
+    /// Serializes in the legacy (witness-stripped) layout regardless of
+    /// whether any input actually carries witness data. This is the form
+    /// hashed to derive the txid, per BIP-141.
+    fn legacy_bytes(&self) -> Vec<u8> {
+        self.serialize(false)
+    }
+
+    fn serialize(&self, segwit: bool) -> Vec<u8> {
+        // Legacy format: version, CompactSize input count, inputs,
+        // CompactSize output count, outputs, lock time.
+        //
+        // SegWit format (BIP-141): version, marker (0x00), flag (0x01), then
+        // the same input/output layout, followed by one witness stack per
+        // input, then lock time.
         let mut result = Vec::new();
 
         // Version (4 bytes LE)
         result.extend(&self.version.to_le_bytes());
 
+        if segwit {
+            result.push(0x00); // marker
+            result.push(0x01); // flag
+        }
+
         // CompactSize for number of inputs
         result.extend(encode_compact_size(self.inputs.len() as u64));
 
@@ -312,6 +588,21 @@ impl BitcoinTransaction {
             result.extend(input.to_bytes());
         }
 
+        // CompactSize for number of outputs
+        result.extend(encode_compact_size(self.outputs.len() as u64));
+
+        // Outputs
+        for output in &self.outputs {
+            result.extend(output.to_bytes());
+        }
+
+        // Witnesses, one stack per input, in input order
+        if segwit {
+            for input in &self.inputs {
+                result.extend(input.witness.to_bytes());
+            }
+        }
+
         // Lock time (4 bytes LE)
         result.extend(&self.lock_time.to_le_bytes());
 
@@ -324,18 +615,59 @@ impl BitcoinTransaction {
         }
 
         let version = u32::from_le_bytes(bytes[0..4].try_into().unwrap());
+        let mut offset = 4;
+
+        // A SegWit transaction announces itself with a 0x00 marker byte
+        // where the legacy input-count CompactSize would start, followed by
+        // a nonzero flag byte.
+        let segwit = bytes.len() > offset + 1 && bytes[offset] == 0x00 && bytes[offset + 1] != 0x00;
+        if segwit {
+            offset += 2;
+        }
 
         // Read CompactSize for input count
-        let (input_count, mut offset) = decode_compact_size(&bytes[4..])?;
-        offset += 4; // because CompactSize was after the 4-byte version
+        let (input_count, input_count_len) = decode_compact_size(&bytes[offset..])?;
+        offset += input_count_len;
+
+        // A crafted message can claim billions of inputs in a handful of bytes; refuse
+        // any count that could not possibly fit in the bytes remaining, so we never
+        // allocate or loop based on an untrusted count (Zebra's TrustedPreallocate idea).
+        let max_inputs = (bytes.len() - offset) / MIN_INPUT_SERIALIZED_SIZE;
+        if input_count as usize > max_inputs {
+            return Err(BitcoinError::InvalidFormat);
+        }
 
-        let mut inputs = Vec::new();
+        let mut inputs = Vec::with_capacity(input_count as usize);
         for _ in 0..input_count {
             let (input, consumed) = TransactionInput::from_bytes(&bytes[offset..])?;
             inputs.push(input);
             offset += consumed;
         }
 
+        // Read CompactSize for output count
+        let (output_count, output_count_len) = decode_compact_size(&bytes[offset..])?;
+        offset += output_count_len;
+
+        let max_outputs = (bytes.len() - offset) / MIN_OUTPUT_SERIALIZED_SIZE;
+        if output_count as usize > max_outputs {
+            return Err(BitcoinError::InvalidFormat);
+        }
+
+        let mut outputs = Vec::with_capacity(output_count as usize);
+        for _ in 0..output_count {
+            let (output, consumed) = TransactionOutput::from_bytes(&bytes[offset..])?;
+            outputs.push(output);
+            offset += consumed;
+        }
+
+        if segwit {
+            for input in &mut inputs {
+                let (witness, consumed) = Witness::from_bytes(&bytes[offset..])?;
+                input.witness = witness;
+                offset += consumed;
+            }
+        }
+
         if bytes.len() < offset + 4 {
             return Err(BitcoinError::InsufficientBytes);
         }
@@ -343,7 +675,35 @@ impl BitcoinTransaction {
         let lock_time = u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap());
         offset += 4;
 
-        Ok((BitcoinTransaction::new(version, inputs, lock_time), offset))
+        Ok((
+            BitcoinTransaction::new(version, inputs, outputs, lock_time),
+            offset,
+        ))
+    }
+}
+
+impl Encode for BitcoinTransaction {
+    fn consensus_encode<W: Write>(&self, writer: &mut W) -> Result<usize, BitcoinError> {
+        let bytes = self.to_bytes();
+        writer
+            .write_all(&bytes)
+            .map_err(|_| BitcoinError::InvalidFormat)?;
+        Ok(bytes.len())
+    }
+}
+
+impl Decode for BitcoinTransaction {
+    fn consensus_decode<R: Read>(reader: &mut R) -> Result<Self, BitcoinError> {
+        // Unlike the other types, `from_bytes` needs to see the whole remaining
+        // buffer up front to enforce the preallocation guard on input/output counts
+        // (see `MIN_INPUT_SERIALIZED_SIZE`), so we read the rest of the stream into
+        // a buffer before delegating, rather than decoding field-by-field.
+        let mut buf = Vec::new();
+        reader
+            .read_to_end(&mut buf)
+            .map_err(|_| BitcoinError::InsufficientBytes)?;
+        let (tx, _) = Self::from_bytes(&buf)?;
+        Ok(tx)
     }
 }
 
@@ -368,6 +728,141 @@ impl fmt::Display for BitcoinTransaction {
             )?;
             writeln!(f, "  Sequence: {}", input.sequence)?;
         }
+        for (i, output) in self.outputs.iter().enumerate() {
+            writeln!(f, "Output #{}", i)?;
+            writeln!(f, "  Value: {}", output.value)?;
+            writeln!(
+                f,
+                "  ScriptPubKey ({} bytes): {}",
+                output.script_pubkey.bytes.len(),
+                hex::encode(&output.script_pubkey.bytes)
+            )?;
+        }
         writeln!(f, "Lock Time: {}", self.lock_time)
     }
 }
+
+/// An 80-byte block header: enough to verify proof-of-work without the
+/// block's full transaction list.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct BlockHeader {
+    pub version: u32,
+    pub prev_blockhash: [u8; 32],
+    pub merkle_root: [u8; 32],
+    pub time: u32,
+    pub bits: u32,
+    pub nonce: u32,
+}
+
+impl BlockHeader {
+    pub fn new(
+        version: u32,
+        prev_blockhash: [u8; 32],
+        merkle_root: [u8; 32],
+        time: u32,
+        bits: u32,
+        nonce: u32,
+    ) -> Self {
+        BlockHeader {
+            version,
+            prev_blockhash,
+            merkle_root,
+            time,
+            bits,
+            nonce,
+        }
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        // Fixed 80-byte layout: version, prev_blockhash, merkle_root, time, bits, nonce.
+        let mut result = Vec::with_capacity(80);
+        result.extend(&self.version.to_le_bytes());
+        result.extend(&self.prev_blockhash);
+        result.extend(&self.merkle_root);
+        result.extend(&self.time.to_le_bytes());
+        result.extend(&self.bits.to_le_bytes());
+        result.extend(&self.nonce.to_le_bytes());
+        result
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<(Self, usize), BitcoinError> {
+        if bytes.len() < 80 {
+            return Err(BitcoinError::InsufficientBytes);
+        }
+        let version = u32::from_le_bytes(bytes[0..4].try_into().unwrap());
+        let mut prev_blockhash = [0u8; 32];
+        prev_blockhash.copy_from_slice(&bytes[4..36]);
+        let mut merkle_root = [0u8; 32];
+        merkle_root.copy_from_slice(&bytes[36..68]);
+        let time = u32::from_le_bytes(bytes[68..72].try_into().unwrap());
+        let bits = u32::from_le_bytes(bytes[72..76].try_into().unwrap());
+        let nonce = u32::from_le_bytes(bytes[76..80].try_into().unwrap());
+        Ok((
+            BlockHeader::new(version, prev_blockhash, merkle_root, time, bits, nonce),
+            80,
+        ))
+    }
+
+    /// Expands the compact `bits` field into the full 256-bit proof-of-work
+    /// threshold, as `[u64; 4]` little-endian limbs (`target()[0]` holds the
+    /// least-significant 64 bits). A block's hash, interpreted the same way,
+    /// must be less than or equal to this value.
+    pub fn target(&self) -> [u64; 4] {
+        expand_compact_target(self.bits)
+    }
+
+    /// This header's block hash: double-SHA-256 of the 80-byte serialization,
+    /// byte-reversed for display, matching the existing `txid`/`wtxid` convention.
+    pub fn block_hash(&self) -> [u8; 32] {
+        let mut hash = double_sha256(&self.to_bytes());
+        hash.reverse();
+        hash
+    }
+}
+
+/// Expands Bitcoin's compact "nBits" difficulty encoding into a 256-bit
+/// target, as `rust-bitcoin`'s `BlockHeader::target` does: the high byte of
+/// `bits` is the exponent and the low three bytes are the mantissa, giving
+/// `target = mantissa << (8 * (exponent - 3))` for `exponent >= 3` (and a
+/// right shift otherwise). A mantissa with its top bit set (`> 0x7FFFFF`) is
+/// the "negative" encoding, which has no valid target and is treated as zero.
+fn expand_compact_target(bits: u32) -> [u64; 4] {
+    let exponent = (bits >> 24) as i32;
+    let mantissa = bits & 0x00FF_FFFF;
+
+    if mantissa > 0x7F_FFFF {
+        return [0, 0, 0, 0];
+    }
+
+    if exponent >= 3 {
+        shift_u64_left_to_u256(mantissa as u64, 8 * (exponent - 3) as u32)
+    } else {
+        let shift = 8 * (3 - exponent) as u32;
+        [(mantissa as u64) >> shift, 0, 0, 0]
+    }
+}
+
+/// Shifts a `u64` left by `shift` bits, spreading the result across a
+/// `[u64; 4]` of little-endian limbs (index 0 least significant).
+fn shift_u64_left_to_u256(value: u64, shift: u32) -> [u64; 4] {
+    if shift >= 256 || value == 0 {
+        return [0; 4];
+    }
+
+    let word_shift = (shift / 64) as usize;
+    let bit_shift = shift % 64;
+    let mut limbs = [0u64; 4];
+
+    if word_shift < 4 {
+        limbs[word_shift] = if bit_shift == 0 {
+            value
+        } else {
+            value << bit_shift
+        };
+    }
+    if bit_shift > 0 && word_shift + 1 < 4 {
+        limbs[word_shift + 1] = value >> (64 - bit_shift);
+    }
+
+    limbs
+}